@@ -0,0 +1,18 @@
+//! Ring-LWE key exchange (Peikert's reconciliation-based construction),
+//! over `Z_q[x] / (x^128 + 1)`.
+//!
+//! See [`protocol`] for the exchange itself and [`wire`] for serializing
+//! the public values it produces.
+
+mod harness;
+mod modint;
+mod ntt;
+mod poly;
+mod protocol;
+mod wire;
+
+pub use harness::{measure_error_rate, ErrorRateReport};
+pub use modint::{ModInt, Modulus};
+pub use poly::Poly;
+pub use protocol::{init_params, initiator_step, responder_step, Fq, KeyPair, Q64513};
+pub use wire::{WireLengthError, PUBLIC_KEY_LEN, RECONCILIATION_LEN};
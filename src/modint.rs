@@ -0,0 +1,110 @@
+//! Generic modular integer, parameterized over a compile-time modulus.
+//!
+//! This follows the `ModInt<Mod>` / `ConstantModulo` pattern common to
+//! modular-arithmetic crates: a zero-sized marker type carries the modulus
+//! as an associated constant, and `ModInt<M>` wraps a reduced value with
+//! `Add`/`Sub`/`Mul` performing the reduction. Swapping the protocol to a
+//! different prime is then a matter of defining a new `Modulus` impl
+//! instead of editing arithmetic scattered across the crate.
+
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, ConstantTimeLess};
+
+/// A compile-time modulus usable as the parameter of [`ModInt`].
+pub trait Modulus: Copy + Clone + PartialEq + Eq {
+    const Q: u16;
+}
+
+/// An integer modulo `M::Q`, always kept reduced to `0..M::Q`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ModInt<M: Modulus>(u16, PhantomData<M>);
+
+impl<M: Modulus> ModInt<M> {
+    pub const fn new(value: u16) -> Self {
+        Self(value % M::Q, PhantomData)
+    }
+
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+
+    pub const fn modulus() -> u16 {
+        M::Q
+    }
+}
+
+impl<M: Modulus> Default for ModInt<M> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<M: Modulus> Add for ModInt<M> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        // `%` compiles to a hardware divide, whose latency on common
+        // targets depends on its operands; a single conditional subtract
+        // reduces `self.0 + other.0` (always `< 2*M::Q`) without one.
+        let sum = self.0 as u32 + other.0 as u32;
+        let reduced = sum.wrapping_sub(M::Q as u32);
+        let no_reduction_needed = sum.ct_lt(&(M::Q as u32));
+        let result = u32::conditional_select(&reduced, &sum, no_reduction_needed);
+        Self(result as u16, PhantomData)
+    }
+}
+
+impl<M: Modulus> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<M: Modulus> Sub for ModInt<M> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        // same reasoning as `Add`: a conditional add-back instead of `%`.
+        let diff = (self.0 as u32).wrapping_sub(other.0 as u32);
+        let corrected = diff.wrapping_add(M::Q as u32);
+        let borrowed = self.0.ct_lt(&other.0);
+        let result = u32::conditional_select(&diff, &corrected, borrowed);
+        Self(result as u16, PhantomData)
+    }
+}
+
+impl<M: Modulus> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<M: Modulus> Mul for ModInt<M> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        Self::new((self.0 as u32 * other.0 as u32 % M::Q as u32) as u16)
+    }
+}
+
+impl<M: Modulus> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<M: Modulus> ConstantTimeEq for ModInt<M> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<M: Modulus> ConstantTimeGreater for ModInt<M> {
+    fn ct_gt(&self, other: &Self) -> Choice {
+        self.0.ct_gt(&other.0)
+    }
+}
+
+impl<M: Modulus> ConstantTimeLess for ModInt<M> {
+    fn ct_lt(&self, other: &Self) -> Choice {
+        self.0.ct_lt(&other.0)
+    }
+}
@@ -0,0 +1,139 @@
+//! Negacyclic number-theoretic transform for `Z_q[x] / (x^n + 1)`, `n = 128`.
+//!
+//! `Q` is chosen so that `2n | (q - 1)`, which guarantees a primitive
+//! `2n`-th root of unity `psi` mod `q` exists. Pre-weighting the inputs by
+//! powers of `psi` and post-weighting the result by powers of `psi^-1`
+//! folds the reduction against `x^n + 1` into the transform itself, so no
+//! separate polynomial remainder step is needed (see Longa & Naehrig,
+//! "Speeding up the Number Theoretic Transform for Faster Ideal Lattice-Based
+//! Cryptography").
+
+pub const N: usize = 128;
+
+/// The only modulus the precomputed twiddle tables below are valid for.
+/// Callers with a different (even NTT-friendly) modulus must fall back to
+/// schoolbook multiplication, since `PSI`/`OMEGA` are specific to this `Q`.
+///
+/// `64513` is the largest 16-bit prime with `2n | (q - 1)`; maximizing `Q`
+/// maximizes the reconciliation margin (which scales with `Q`) against the
+/// noise (which doesn't), see `protocol::NOISE_BOUND_DIVISOR`.
+pub const SUPPORTED_Q: u16 = 64513;
+
+const Q: u16 = SUPPORTED_Q;
+
+const fn mod_pow(mut base: u32, mut exp: u32, q: u32) -> u32 {
+    let mut result = 1u32;
+    base %= q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % q;
+        }
+        exp >>= 1;
+        base = base * base % q;
+    }
+    result
+}
+
+const fn mod_inv(a: u32, q: u32) -> u32 {
+    // q is prime, so a^(q-2) == a^-1 (mod q) by Fermat's little theorem.
+    mod_pow(a, q - 2, q)
+}
+
+/// Primitive `2n`-th root of unity mod `Q`, used for the negacyclic weighting.
+const PSI: u32 = 45056;
+/// Primitive `n`-th root of unity mod `Q`, i.e. `PSI^2`.
+const OMEGA: u32 = 12565;
+
+const fn powers(base: u32, q: u32) -> [u16; N] {
+    let mut out = [0u16; N];
+    let mut acc = 1u32;
+    let mut i = 0;
+    while i < N {
+        out[i] = acc as u16;
+        acc = acc * base % q;
+        i += 1;
+    }
+    out
+}
+
+/// Twiddle tables precomputed once for the fixed `(Q, N)` pair.
+struct Twiddles {
+    psi_pows: [u16; N],
+    psi_inv_pows: [u16; N],
+    omega_pows: [u16; N],
+    omega_inv_pows: [u16; N],
+    n_inv: u16,
+}
+
+const TWIDDLES: Twiddles = Twiddles {
+    psi_pows: powers(PSI, Q as u32),
+    psi_inv_pows: powers(mod_inv(PSI, Q as u32), Q as u32),
+    omega_pows: powers(OMEGA, Q as u32),
+    omega_inv_pows: powers(mod_inv(OMEGA, Q as u32), Q as u32),
+    n_inv: mod_inv(N as u32, Q as u32) as u16,
+};
+
+fn bit_reverse_permute(a: &mut [u16; N]) {
+    let bits = N.trailing_zeros();
+    for i in 0..N {
+        let j = (i as u32).reverse_bits() >> (32 - bits);
+        if j as usize > i {
+            a.swap(i, j as usize);
+        }
+    }
+}
+
+/// In-place iterative Cooley-Tukey NTT using the `omega` powers in
+/// `twiddles`. Takes natural-order input (bit-reversing it first, before
+/// the butterflies) and produces natural-order output.
+fn transform(a: &mut [u16; N], twiddles: &[u16; N]) {
+    bit_reverse_permute(a);
+    let q = Q as u32;
+    let mut len = 2;
+    while len <= N {
+        let step = N / len;
+        let half = len / 2;
+        let mut start = 0;
+        while start < N {
+            for i in 0..half {
+                let w = twiddles[i * step] as u32;
+                let u = a[start + i] as u32;
+                let v = (a[start + i + half] as u32) * w % q;
+                a[start + i] = ((u + v) % q) as u16;
+                a[start + i + half] = ((u + q - v) % q) as u16;
+            }
+            start += len;
+        }
+        len <<= 1;
+    }
+}
+
+/// Multiply two length-`N` coefficient vectors mod `x^N + 1` via the
+/// negacyclic NTT. Inputs and output are in standard (non-transformed)
+/// coefficient order.
+pub fn negacyclic_mul(a: &[u16; N], b: &[u16; N]) -> [u16; N] {
+    let q = Q as u32;
+    let mut fa = *a;
+    let mut fb = *b;
+    for ((a, b), &psi) in fa.iter_mut().zip(fb.iter_mut()).zip(TWIDDLES.psi_pows.iter()) {
+        *a = (*a as u32 * psi as u32 % q) as u16;
+        *b = (*b as u32 * psi as u32 % q) as u16;
+    }
+
+    transform(&mut fa, &TWIDDLES.omega_pows);
+    transform(&mut fb, &TWIDDLES.omega_pows);
+
+    let mut fc = [0u16; N];
+    for ((c, &a), &b) in fc.iter_mut().zip(fa.iter()).zip(fb.iter()) {
+        *c = (a as u32 * b as u32 % q) as u16;
+    }
+
+    transform(&mut fc, &TWIDDLES.omega_inv_pows);
+
+    let n_inv = TWIDDLES.n_inv as u32;
+    for (c, &psi_inv) in fc.iter_mut().zip(TWIDDLES.psi_inv_pows.iter()) {
+        let scaled = *c as u32 * n_inv % q;
+        *c = (scaled * psi_inv as u32 % q) as u16;
+    }
+    fc
+}
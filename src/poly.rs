@@ -0,0 +1,224 @@
+//! The ring element type `Poly<T>` and its arithmetic, generic over the
+//! coefficient type so the same code serves both `Poly<ModInt<M>>` (ring
+//! elements) and `Poly<bool>` (reconciliation vectors).
+
+use core::fmt;
+use core::ops::{AddAssign, BitXor, Mul, MulAssign, SubAssign};
+use rand::Rng;
+use subtle::{Choice, ConstantTimeEq, ConstantTimeLess};
+
+use crate::modint::{ModInt, Modulus};
+use crate::ntt;
+
+#[derive(Clone, Copy)]
+pub struct Poly<T>(pub(crate) [T; 128]);
+
+impl<T: Default + Copy> Default for Poly<T> {
+    fn default() -> Self {
+        Self([T::default(); 128])
+    }
+}
+
+impl<T: AddAssign + Copy> AddAssign for Poly<T> {
+    fn add_assign(&mut self, other: Self) {
+        for (i, j) in self.0.iter_mut().zip(other.0.iter()) {
+            *i += *j;
+        }
+    }
+}
+
+impl<T: SubAssign + Copy> SubAssign for Poly<T> {
+    fn sub_assign(&mut self, other: Self) {
+        for (i, j) in self.0.iter_mut().zip(other.0.iter()) {
+            *i -= *j;
+        }
+    }
+}
+
+impl<T: MulAssign + Copy> MulAssign<T> for Poly<T> {
+    fn mul_assign(&mut self, other: T) {
+        self.0.iter_mut().for_each(|i| *i *= other);
+    }
+}
+
+/// Schoolbook O(n^2) convolution followed by reduction against `x^128 + 1`.
+/// Works for any modulus; kept as a fallback for moduli the NTT twiddle
+/// tables in `ntt` were not precomputed for.
+fn mul_schoolbook<M: Modulus>(a: &Poly<ModInt<M>>, b: &Poly<ModInt<M>>) -> Poly<ModInt<M>> {
+    let mut tmp = [ModInt::<M>::default(); 255];
+    for (n, &i) in a.0.iter().enumerate() {
+        for (m, &j) in b.0.iter().enumerate() {
+            tmp[n + m] += i * j;
+        }
+    }
+
+    // reduce mod (x^128 + 1): x^128 == -1, so the high half folds back
+    // negated onto the low half (`tmp[128 + i]` onto coefficient `i`, for
+    // `i < 127`); `tmp` has no term past degree 254, so coefficient 127
+    // needs no folding.
+    let mut ret = Poly::default();
+    let (low, high) = tmp.split_at(128);
+    ret.0.copy_from_slice(low);
+    for (i, &h) in high.iter().enumerate() {
+        ret.0[i] -= h;
+    }
+    ret
+}
+
+impl<M: Modulus> Mul for &Poly<ModInt<M>> {
+    type Output = Poly<ModInt<M>>;
+    fn mul(self, other: Self) -> Poly<ModInt<M>> {
+        if M::Q == ntt::SUPPORTED_Q {
+            let a = self.0.map(ModInt::value);
+            let b = other.0.map(ModInt::value);
+            Poly(ntt::negacyclic_mul(&a, &b).map(ModInt::new))
+        } else {
+            mul_schoolbook(self, other)
+        }
+    }
+}
+
+impl BitXor for &Poly<bool> {
+    type Output = Poly<bool>;
+    fn bitxor(self, rhs: Self) -> Poly<bool> {
+        let mut ret = Poly::default();
+        ret.0.iter_mut().zip(self.0.iter().zip(rhs.0.iter()))
+            .for_each(|(r, (&i, &j))| *r = i != j);
+        ret
+    }
+}
+
+impl ConstantTimeEq for Poly<bool> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.iter().zip(other.0.iter()).fold(Choice::from(1u8), |acc, (&a, &b)| {
+            acc & (a as u8).ct_eq(&(b as u8))
+        })
+    }
+}
+
+impl Poly<bool> {
+    /// Returns the shared secret bits if `self` and `other` agree on every
+    /// bit, otherwise `None`. Runs in constant time, unlike `self.0 !=
+    /// other.0`, which can short-circuit on the first differing bit.
+    pub fn agree(&self, other: &Self) -> Option<Self> {
+        if bool::from(self.ct_eq(other)) {
+            Some(*self)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Poly<bool> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &i in self.0.iter() {
+            f.write_str(if i { "*" } else { " " })?;
+        }
+        Ok(())
+    }
+}
+
+impl<M: Modulus> fmt::Display for Poly<ModInt<M>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in self.0.iter() {
+            write!(f, "{:04x}", i.value())?;
+        }
+        Ok(())
+    }
+}
+
+/// Draws a uniform value in `0..bound` in constant time. `rng.gen_range`
+/// uses Lemire's rejection sampling, whose iteration count depends on the
+/// drawn bits; this instead always pulls a single wide sample and reduces
+/// it mod `bound`, at the cost of a (negligible for our small `bound`) bias.
+pub(crate) fn sample_uniform<R: Rng>(rng: &mut R, bound: u16) -> u16 {
+    (rng.r#gen::<u32>() % (bound as u32)) as u16
+}
+
+impl<M: Modulus> Poly<ModInt<M>> {
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        let mut ret = Self::default();
+        ret.0.iter_mut().for_each(|i| *i = ModInt::new(sample_uniform(rng, M::Q)));
+        ret
+    }
+}
+
+/// Returns the `Choice` for `lo <= x && x < hi`, without branching on `x`.
+pub(crate) fn ct_in_range(x: u16, lo: u16, hi: u16) -> Choice {
+    (!x.ct_lt(&lo)) & x.ct_lt(&hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    struct TestModulus;
+    impl Modulus for TestModulus {
+        const Q: u16 = ntt::SUPPORTED_Q;
+    }
+
+    /// `mul_schoolbook` must agree with the NTT path for the same modulus;
+    /// a bug in the O(n^2) fallback would otherwise only surface once some
+    /// future non-NTT-friendly `Modulus` impl started using it.
+    #[test]
+    fn schoolbook_matches_ntt() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..20 {
+            let a = Poly::<ModInt<TestModulus>>::random(&mut rng);
+            let b = Poly::<ModInt<TestModulus>>::random(&mut rng);
+            let via_ntt = &a * &b;
+            let via_schoolbook = mul_schoolbook(&a, &b);
+            assert_eq!(
+                via_ntt.0.map(ModInt::value),
+                via_schoolbook.0.map(ModInt::value),
+            );
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    struct OtherModulus;
+    impl Modulus for OtherModulus {
+        const Q: u16 = 7681;
+    }
+
+    /// Reference negacyclic convolution computed independently of
+    /// `mul_schoolbook`, for cross-checking the generic `Mul` impl against
+    /// a modulus this crate has no twiddle tables for.
+    fn reference_negacyclic_mul(a: &[u16; 128], b: &[u16; 128], q: u16) -> [u16; 128] {
+        let mut tmp = [0i64; 255];
+        for (n, &i) in a.iter().enumerate() {
+            for (m, &j) in b.iter().enumerate() {
+                tmp[n + m] += i as i64 * j as i64;
+            }
+        }
+        let mut ret = [0u16; 128];
+        for (i, r) in ret.iter_mut().enumerate().take(127) {
+            *r = (tmp[i] - tmp[128 + i]).rem_euclid(q as i64) as u16;
+        }
+        ret[127] = tmp[127].rem_euclid(q as i64) as u16;
+        ret
+    }
+
+    /// `chunk0-3` made `Poly`/`Mul` generic over any `Modulus` impl, not
+    /// just the NTT-friendly shipped prime: exercise that with a modulus
+    /// this crate has no twiddle tables for, so the generic dispatch can
+    /// only go through `mul_schoolbook`, against an independent reference.
+    #[test]
+    fn generic_modulus_matches_reference() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..20 {
+            let a = Poly::<ModInt<OtherModulus>>::random(&mut rng);
+            let b = Poly::<ModInt<OtherModulus>>::random(&mut rng);
+            let got = (&a * &b).0.map(ModInt::value);
+            let expected = reference_negacyclic_mul(
+                &a.0.map(ModInt::value),
+                &b.0.map(ModInt::value),
+                OtherModulus::Q,
+            );
+            assert_eq!(got, expected);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! The ring-LWE key exchange protocol: parameter setup, key generation, and
+//! the reconciliation-based shared-secret derivation.
+//!
+//! A run looks like this:
+//!
+//! ```
+//! use rand::SeedableRng;
+//! use rand_chacha::ChaCha8Rng;
+//! use rlwe_kex::{init_params, initiator_step, responder_step, KeyPair};
+//!
+//! let mut rng = ChaCha8Rng::seed_from_u64(1);
+//! let a = init_params(&mut rng);
+//!
+//! // initiator (Alice)
+//! let alice = KeyPair::generate(&a, &mut rng);
+//!
+//! // responder (Bob), after receiving `alice.public()`
+//! let (bob, w) = responder_step(&a, &mut rng);
+//! let bob_key = initiator_step(&bob, &alice.public(), &w);
+//!
+//! // initiator, after receiving `(bob.public(), w)`
+//! let alice_key = initiator_step(&alice, &bob.public(), &w);
+//! assert!(alice_key.agree(&bob_key).is_some());
+//! ```
+
+use rand::Rng;
+
+use crate::modint::{ModInt, Modulus};
+use crate::poly::{ct_in_range, sample_uniform, Poly};
+
+/// The NTT-friendly prime modulus used by this protocol, i.e.
+/// `2*128 | (Q - 1)`. `Poly`, `gen_noise` and `compute_w` are generic over
+/// the `Modulus`; `KeyPair`, `init_params`, `responder_step` and
+/// `initiator_step` work on `Fq` only, so switching to a different 16-bit
+/// prime means defining another `Modulus` impl and pointing `Fq` at it.
+/// The noise bound `Q / NOISE_BOUND_DIVISOR` is 0 for primes below 32256,
+/// and `gen_noise` panics for those.
+///
+/// `64513` (rather than a smaller NTT-friendly prime like `12289`) is the
+/// largest one that fits in `u16`: the two parties' shared secrets differ
+/// by a noise term whose magnitude doesn't grow with `Q` (see
+/// `NOISE_BOUND_DIVISOR`), so maximizing `Q` maximizes the reconciliation
+/// margin the noise is measured against, which is the only knob available
+/// for lowering the handshake failure rate without changing the `u16`
+/// coefficient representation or adding a real error-correcting
+/// reconciliation code (see [`crate::measure_error_rate`]).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Q64513;
+
+impl Modulus for Q64513 {
+    const Q: u16 = 64513;
+}
+
+/// The ring coefficient type this protocol is shipped with.
+pub type Fq = ModInt<Q64513>;
+
+/// Divisor applied to the modulus to get the (exclusive) upper bound for
+/// the one-sided coefficients drawn by `gen_noise`, for both the secret
+/// share and the noise term.
+///
+/// The two parties' shared secrets differ by `2*(s_A*e_B - s_B*e_A)`, a
+/// degree-128 convolution of coefficients in `[0, bound)`. `bound = 2`
+/// (the smallest value that doesn't zero out the secret and noise
+/// entirely) is already what this divisor is tuned for at `Q = 64513`;
+/// [`crate::measure_error_rate`] is what it's tuned against.
+const NOISE_BOUND_DIVISOR: u16 = 32256;
+
+fn gen_noise<M: Modulus, R: Rng>(rng: &mut R) -> Poly<ModInt<M>> {
+    let mut ret = Poly::default();
+    ret.0.iter_mut()
+        .for_each(|i| *i = ModInt::new(sample_uniform(rng, M::Q / NOISE_BOUND_DIVISOR)));
+    ret
+}
+
+/// A party's key pair for one exchange: a private share (`s`, `e`) and the
+/// public value `p = a*s + 2e` derived from it.
+#[derive(Clone)]
+pub struct KeyPair {
+    secret: Poly<Fq>,
+    noise: Poly<Fq>,
+    public: Poly<Fq>,
+}
+
+impl KeyPair {
+    /// Generates a fresh key pair against the shared parameter `a`.
+    pub fn generate<R: Rng>(a: &Poly<Fq>, rng: &mut R) -> Self {
+        // `s` must be as small as `e`, not uniform over the whole ring, or
+        // the cross terms in `initiator_step` overwhelm the reconciliation
+        // margin regardless of how small `e` is.
+        let s = gen_noise(rng);
+        let e = gen_noise(rng);
+        let mut e2 = e;
+        e2 *= ModInt::new(2);
+        let mut p = a * &s;
+        p += e2;
+        Self { secret: s, noise: e, public: p }
+    }
+
+    /// The public value to send to the peer.
+    pub fn public(&self) -> Poly<Fq> {
+        self.public
+    }
+
+    /// The private noise term `e`, exposed only for diagnostics (e.g. the
+    /// error-rate harness); never transmitted.
+    pub fn noise(&self) -> Poly<Fq> {
+        self.noise
+    }
+}
+
+/// Uniformly random public parameter `a`, shared out-of-band by both
+/// parties before running the exchange.
+pub fn init_params<R: Rng>(rng: &mut R) -> Poly<Fq> {
+    Poly::random(rng)
+}
+
+fn compute_w<M: Modulus>(Poly(p): Poly<ModInt<M>>) -> Poly<bool> {
+    let q4 = M::Q / 4;
+    let _3q4 = (3 * (M::Q as u32) / 4) as u16;
+    let mut w = Poly::default();
+    for (i, j) in w.0.iter_mut().zip(p.iter()) {
+        let mid = ct_in_range(j.value(), q4, _3q4);
+        *i = bool::from(!mid);
+    }
+    w
+}
+
+/// The responder's turn: generate a key pair against `a`, then derive the
+/// reconciliation vector `w` from its own public value. Send `(keypair.
+/// public(), w)` to the initiator.
+pub fn responder_step<R: Rng>(a: &Poly<Fq>, rng: &mut R) -> (KeyPair, Poly<bool>) {
+    let keypair = KeyPair::generate(a, rng);
+    let w = compute_w(keypair.public());
+    (keypair, w)
+}
+
+/// Derive the shared secret from this party's key pair, the peer's public
+/// value, and the reconciliation vector `w` (computed by whichever side
+/// called [`responder_step`]). Both sides call this with the same `w` and
+/// the peer's public value to end up with the same shared secret in most
+/// runs; the two can still disagree, at the rate [`crate::measure_error_rate`]
+/// measures.
+pub fn initiator_step(keypair: &KeyPair, peer_public: &Poly<Fq>, w: &Poly<bool>) -> Poly<bool> {
+    let q18 = Fq::modulus() / 8;
+    let q38 = (3 * (Fq::modulus() as u32) / 8) as u16;
+    let q58 = (5 * (Fq::modulus() as u32) / 8) as u16;
+    let q78 = (7 * (Fq::modulus() as u32) / 8) as u16;
+
+    let tmp = &keypair.secret * peer_public;
+
+    let mut ret = Poly::default();
+    for (r, (&wi, &j)) in ret.0.iter_mut().zip(w.0.iter().zip(tmp.0.iter())) {
+        // region [3q/8..5q/8), used where w = 0 (responder in [q/4..3q/4))
+        let narrow = ct_in_range(j.value(), q38, q58);
+        // region [q/8..7q/8), used where w = 1 (responder outside [q/4..3q/4))
+        let wide = ct_in_range(j.value(), q18, q78);
+        let use_wide = subtle::Choice::from(wi as u8);
+        let in_range = (narrow & !use_wide) | (wide & use_wide);
+        *r = bool::from(!in_range);
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    /// End-to-end run of the exchange for a fixed seed: both parties must
+    /// land on the same shared secret.
+    #[test]
+    fn full_exchange_agrees_on_shared_secret() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+
+        let a = init_params(&mut rng);
+
+        let alice = KeyPair::generate(&a, &mut rng);
+        let (bob, w) = responder_step(&a, &mut rng);
+        let bob_key = initiator_step(&bob, &alice.public(), &w);
+        let alice_key = initiator_step(&alice, &bob.public(), &w);
+
+        assert!(alice_key.agree(&bob_key).is_some());
+    }
+}
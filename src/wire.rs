@@ -0,0 +1,117 @@
+//! Compact byte encoding for the public values exchanged over the wire:
+//! the public key `Poly<Fq>` and the reconciliation vector `Poly<bool>`.
+
+use byteorder::{ByteOrder, LittleEndian};
+use core::fmt;
+
+use crate::modint::ModInt;
+use crate::poly::Poly;
+use crate::protocol::Fq;
+
+/// Serialized size of a `Poly<Fq>`: one little-endian `u16` per coefficient.
+pub const PUBLIC_KEY_LEN: usize = 128 * 2;
+
+/// Serialized size of a `Poly<bool>`: one bit per coefficient, packed
+/// LSB-first.
+pub const RECONCILIATION_LEN: usize = 128 / 8;
+
+/// The input to a `from_bytes` parser had the wrong length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WireLengthError {
+    pub expected: usize,
+    pub got: usize,
+}
+
+impl fmt::Display for WireLengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} bytes, got {}", self.expected, self.got)
+    }
+}
+
+impl std::error::Error for WireLengthError {}
+
+impl Poly<Fq> {
+    pub fn to_bytes(&self) -> [u8; PUBLIC_KEY_LEN] {
+        let mut out = [0u8; PUBLIC_KEY_LEN];
+        for (chunk, c) in out.chunks_exact_mut(2).zip(self.0.iter()) {
+            LittleEndian::write_u16(chunk, c.value());
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireLengthError> {
+        if bytes.len() != PUBLIC_KEY_LEN {
+            return Err(WireLengthError { expected: PUBLIC_KEY_LEN, got: bytes.len() });
+        }
+        let mut ret = Self::default();
+        for (c, chunk) in ret.0.iter_mut().zip(bytes.chunks_exact(2)) {
+            *c = ModInt::new(LittleEndian::read_u16(chunk));
+        }
+        Ok(ret)
+    }
+}
+
+impl Poly<bool> {
+    pub fn to_bytes(&self) -> [u8; RECONCILIATION_LEN] {
+        let mut out = [0u8; RECONCILIATION_LEN];
+        for (i, &bit) in self.0.iter().enumerate() {
+            if bit {
+                out[i / 8] |= 1 << (i % 8);
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireLengthError> {
+        if bytes.len() != RECONCILIATION_LEN {
+            return Err(WireLengthError { expected: RECONCILIATION_LEN, got: bytes.len() });
+        }
+        let mut ret = Self::default();
+        for (i, bit) in ret.0.iter_mut().enumerate() {
+            *bit = (bytes[i / 8] >> (i % 8)) & 1 == 1;
+        }
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn public_key_round_trips() {
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let p = Poly::<Fq>::random(&mut rng);
+        let bytes = p.to_bytes();
+        assert_eq!(bytes.len(), PUBLIC_KEY_LEN);
+        let back = Poly::<Fq>::from_bytes(&bytes).unwrap();
+        assert_eq!(p.0.map(ModInt::value), back.0.map(ModInt::value));
+    }
+
+    #[test]
+    fn reconciliation_vector_round_trips() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        let mut w = Poly::<bool>::default();
+        for bit in w.0.iter_mut() {
+            *bit = rng.r#gen();
+        }
+        let bytes = w.to_bytes();
+        assert_eq!(bytes.len(), RECONCILIATION_LEN);
+        let back = Poly::<bool>::from_bytes(&bytes).unwrap();
+        assert_eq!(w.0, back.0);
+    }
+
+    #[test]
+    fn from_bytes_rejects_wrong_length() {
+        assert_eq!(
+            Poly::<Fq>::from_bytes(&[0u8; PUBLIC_KEY_LEN - 1]).err(),
+            Some(WireLengthError { expected: PUBLIC_KEY_LEN, got: PUBLIC_KEY_LEN - 1 }),
+        );
+        assert_eq!(
+            Poly::<bool>::from_bytes(&[0u8; RECONCILIATION_LEN + 1]).err(),
+            Some(WireLengthError { expected: RECONCILIATION_LEN, got: RECONCILIATION_LEN + 1 }),
+        );
+    }
+}